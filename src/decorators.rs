@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::traits::{Greeter, HasError, NamedPerson, PersonContext, PersonQuerier};
+
+/// A [`Greeter`] wrapper that logs the resolved name before delegating to the
+/// inner greeter.
+pub struct LoggingGreeter<Inner> {
+    inner: Inner,
+}
+
+impl<Inner> LoggingGreeter<Inner> {
+    pub fn new(inner: Inner) -> Self {
+        LoggingGreeter { inner }
+    }
+}
+
+impl<Context, Inner> Greeter<Context> for LoggingGreeter<Inner>
+where
+    Context: PersonQuerier,
+    Inner: Greeter<Context>,
+{
+    fn greet(
+        &self,
+        context: &Context,
+        person_id: &Context::PersonId,
+    ) -> Result<(), Context::Error> {
+        let person = context.query_person(person_id)?;
+        println!("greeting {}", person.name());
+        self.inner.greet(context, person_id)
+    }
+}
+
+/// A [`PersonQuerier`] wrapper that memoizes results, falling through to the
+/// inner querier only on a cache miss.
+pub struct CachingPersonQuerier<Inner>
+where
+    Inner: PersonContext + HasError,
+{
+    inner: Inner,
+    cache: RefCell<HashMap<Inner::PersonId, Inner::Person>>,
+}
+
+impl<Inner> CachingPersonQuerier<Inner>
+where
+    Inner: PersonContext + HasError,
+{
+    pub fn new(inner: Inner) -> Self {
+        CachingPersonQuerier {
+            inner,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<Inner> PersonContext for CachingPersonQuerier<Inner>
+where
+    Inner: PersonContext + HasError,
+{
+    type PersonId = Inner::PersonId;
+    type Person = Inner::Person;
+}
+
+impl<Inner> HasError for CachingPersonQuerier<Inner>
+where
+    Inner: PersonContext + HasError,
+{
+    type Error = Inner::Error;
+}
+
+impl<Inner> PersonQuerier for CachingPersonQuerier<Inner>
+where
+    Inner: PersonQuerier,
+    Inner::PersonId: Eq + Hash + Clone,
+    Inner::Person: Clone,
+{
+    fn query_person(&self, person_id: &Self::PersonId) -> Result<Self::Person, Self::Error> {
+        if let Some(person) = self.cache.borrow().get(person_id) {
+            return Ok(person.clone());
+        }
+
+        let person = self.inner.query_person(person_id)?;
+        self.cache
+            .borrow_mut()
+            .insert(person_id.clone(), person.clone());
+        Ok(person)
+    }
+}
+
+/// A [`Greeter`] wrapper that greets each person at most once per session,
+/// silently refusing to re-greet an id it has already seen.
+pub struct RunOnceGreeter<Inner, Id> {
+    inner: Inner,
+    seen: RefCell<HashSet<Id>>,
+}
+
+impl<Inner, Id> RunOnceGreeter<Inner, Id> {
+    pub fn new(inner: Inner) -> Self {
+        RunOnceGreeter {
+            inner,
+            seen: RefCell::new(HashSet::new()),
+        }
+    }
+}
+
+impl<Context, Inner> Greeter<Context> for RunOnceGreeter<Inner, Context::PersonId>
+where
+    Context: PersonContext + HasError,
+    Inner: Greeter<Context>,
+    Context::PersonId: Eq + Hash + Clone,
+{
+    fn greet(
+        &self,
+        context: &Context,
+        person_id: &Context::PersonId,
+    ) -> Result<(), Context::Error> {
+        if !self.seen.borrow_mut().insert(person_id.clone()) {
+            return Ok(());
+        }
+        self.inner.greet(context, person_id)
+    }
+}
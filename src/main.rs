@@ -1,11 +1,20 @@
-use traits::{PersonQuerier, HasError, PersonContext, Greeter};
+#![feature(associated_type_defaults)]
 
+use traits::{
+    request_ref, AsyncGreeter, AsyncPersonQuerier, BatchGreeter, Greeter, HasError, PersonContext,
+    PersonError, PersonQuerier, PersonRequestGenerator, ProvideError,
+};
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::decorators::{CachingPersonQuerier, LoggingGreeter, RunOnceGreeter};
 use crate::traits::NamedPerson;
+pub mod decorators;
 pub mod traits;
 
-#[derive(Debug)]
-pub struct PersonId(pub String);
-
+#[derive(Clone)]
 pub struct Person {
     pub name: String,
 }
@@ -19,7 +28,7 @@ impl NamedPerson for Person {
 struct AppContext;
 
 impl HasError for AppContext {
-    type Error = anyhow::Error;
+    // use the default, structured PersonError
 }
 
 impl PersonContext for AppContext {
@@ -28,37 +37,192 @@ impl PersonContext for AppContext {
 }
 
 impl PersonQuerier for AppContext {
-    fn query_person(&self, _person_id: &Self::PersonId) -> Result<Person, Self::Error> {
+    fn query_person(&self, person_id: &Self::PersonId) -> Result<Person, Self::Error> {
+        if person_id.is_empty() {
+            // attach structured diagnostics the greeter can surface later
+            return Err(PersonError::not_found(person_id.clone())
+                .with_extension("queried_id", person_id.clone())
+                .with_extension("retry_count", 0));
+        }
+
         Ok(Person {
-            name: format!("{:?}", _person_id),
+            name: person_id.clone(),
         })
     }
 }
 
+impl AsyncPersonQuerier for AppContext {
+    async fn query_person(&self, person_id: &Self::PersonId) -> Result<Person, Self::Error> {
+        // A real context would await a datastore here; we yield once to prove
+        // the plumbing composes across an await point.
+        tokio::task::yield_now().await;
+        PersonQuerier::query_person(self, person_id)
+    }
+}
+
 struct SimpleGreeter;
 
 impl<Context> Greeter<Context> for SimpleGreeter
-    where Context: PersonQuerier,
- {
+where
+    Context: PersonQuerier,
+    Context::Error: ProvideError,
+{
     fn greet(
         &self,
         context: &Context,
         person_id: &Context::PersonId,
     ) -> Result<(), Context::Error> {
-        let person = context.query_person(person_id)?;
-        println!("Hello, {}", person.name());
+        match context.query_person(person_id) {
+            Ok(person) => {
+                println!("Hello, {}", person.name());
+                Ok(())
+            }
+            Err(err) => {
+                // Surface the structured diagnostics attached to the error
+                // without knowing its concrete type.
+                if let Some(extensions) = request_ref::<HashMap<String, Value>, _>(&err) {
+                    eprintln!("failed to greet person: {extensions:?}");
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<Context> BatchGreeter<Context> for SimpleGreeter
+where
+    Context: PersonQuerier,
+    Context::Error: ProvideError,
+{
+    fn greet_all(
+        &self,
+        context: &Context,
+        person_ids: &[Context::PersonId],
+    ) -> Result<(), Context::Error> {
+        for person in context.query_persons(person_ids)? {
+            println!("Hello, {}", person.name());
+        }
         Ok(())
     }
 }
-fn main() -> anyhow::Result<()>{
+
+/// A drainable, splittable pool of person ids for load generation.
+pub struct IdPool {
+    ids: Vec<String>,
+}
+
+impl PersonRequestGenerator for IdPool {
+    type PersonId = String;
+
+    fn split(self) -> (Self, Option<Self>) {
+        if self.ids.len() <= 1 {
+            return (self, None);
+        }
+
+        let mut ids = self.ids;
+        let right = ids.split_off(ids.len() / 2);
+        (IdPool { ids }, Some(IdPool { ids: right }))
+    }
+
+    fn next(&mut self) -> Option<Self::PersonId> {
+        self.ids.pop()
+    }
+}
+
+/// Recursively halve a generator across rayon workers, batching each leaf
+/// through [`PersonQuerier::query_persons`].
+fn resolve_pool<Context>(context: &Context, pool: IdPool) -> Result<Vec<Person>, Context::Error>
+where
+    Context: PersonQuerier<PersonId = String, Person = Person> + Sync,
+    Context::Error: Send,
+{
+    let (mut left, right) = pool.split();
+    match right {
+        Some(right) => {
+            let (left, right) = rayon::join(
+                || resolve_pool(context, left),
+                || resolve_pool(context, right),
+            );
+            let mut persons = left?;
+            persons.extend(right?);
+            Ok(persons)
+        }
+        None => {
+            let mut ids = Vec::new();
+            while let Some(id) = left.next() {
+                ids.push(id);
+            }
+            context.query_persons(&ids)
+        }
+    }
+}
+
+impl<Context> AsyncGreeter<Context> for SimpleGreeter
+where
+    Context: AsyncPersonQuerier,
+    Context::Error: ProvideError,
+{
+    async fn greet(
+        &self,
+        context: &Context,
+        person_id: &Context::PersonId,
+    ) -> Result<(), Context::Error> {
+        match context.query_person(person_id).await {
+            Ok(person) => {
+                println!("Hello, {}", person.name());
+                Ok(())
+            }
+            Err(err) => {
+                if let Some(extensions) = request_ref::<HashMap<String, Value>, _>(&err) {
+                    eprintln!("failed to greet person: {extensions:?}");
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+fn main() {
     let appcontext = AppContext;
     let person_id = "davirain".to_string();
 
     let simple = SimpleGreeter;
 
-    // call greet
-    simple.greet(&appcontext, &person_id)?;
+    // call the synchronous greeter
+    if Greeter::greet(&simple, &appcontext, &person_id).is_err() {
+        eprintln!("greeting failed");
+    }
+
+    // drive the async greeter on a runtime, as a network/DB-backed context would
+    let runtime = tokio::runtime::Runtime::new().expect("failed to build runtime");
+    if runtime
+        .block_on(AsyncGreeter::greet(&simple, &appcontext, &person_id))
+        .is_err()
+    {
+        eprintln!("async greeting failed");
+    }
+
+    // batch-greet a whole slice in one call
+    let ids = vec!["alice".to_string(), "bob".to_string(), "carol".to_string()];
+    if BatchGreeter::greet_all(&simple, &appcontext, &ids).is_err() {
+        eprintln!("batch greeting failed");
+    }
+
+    // split a pool of ids across worker threads for throughput benchmarking
+    let pool = IdPool { ids };
+    match resolve_pool(&appcontext, pool) {
+        Ok(persons) => println!("resolved {} persons", persons.len()),
+        Err(_) => eprintln!("pool resolution failed"),
+    }
+
+    // assemble a stack of reusable layers rather than baking behavior into the
+    // context: caching querier, logging greeter, run-once guard
+    let cached = CachingPersonQuerier::new(AppContext);
+    let greeter: RunOnceGreeter<LoggingGreeter<SimpleGreeter>, String> =
+        RunOnceGreeter::new(LoggingGreeter::new(SimpleGreeter));
+    let _ = Greeter::greet(&greeter, &cached, &person_id);
+    // the second call is a no-op: the run-once guard refuses to re-greet
+    let _ = Greeter::greet(&greeter, &cached, &person_id);
 
     println!("Hello, world!");
-    Ok(())
 }
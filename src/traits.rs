@@ -1,3 +1,10 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use serde_json::Value;
+
 pub trait NamedPerson {
     fn name(&self) -> &str;
 }
@@ -9,11 +16,144 @@ pub trait PersonContext {
 }
 
 pub trait HasError {
-    type Error;
+    /// Defaults to the structured [`PersonError`]; a context may still pick its
+    /// own error type.
+    type Error = PersonError;
+}
+
+/// The concrete failure modes a person lookup can hit.
+#[derive(Debug)]
+pub enum PersonErrorKind {
+    /// No person was found for the queried id.
+    NotFound { id: String },
+    /// An underlying query failed; keeps the source error for `source()`.
+    QueryFailed {
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// A backend reported a failure that has no richer structure.
+    Backend(String),
+}
+
+/// A first-class, introspectable error for person resolution. Downstream
+/// tooling can match on [`kind`](PersonError::kind) while still reading the
+/// arbitrary diagnostic fields attached to [`extensions`](PersonError::extensions).
+#[derive(Debug)]
+pub struct PersonError {
+    kind: PersonErrorKind,
+    extensions: HashMap<String, Value>,
+}
+
+impl PersonError {
+    fn new(kind: PersonErrorKind) -> Self {
+        PersonError {
+            kind,
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// No person was found for `id`.
+    pub fn not_found(id: impl Into<String>) -> Self {
+        PersonError::new(PersonErrorKind::NotFound { id: id.into() })
+    }
+
+    /// Wrap an underlying query failure.
+    pub fn query_failed(source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        PersonError::new(PersonErrorKind::QueryFailed {
+            source: source.into(),
+        })
+    }
+
+    /// A backend failure with no richer structure.
+    pub fn backend(message: impl Into<String>) -> Self {
+        PersonError::new(PersonErrorKind::Backend(message.into()))
+    }
+
+    /// Attach a structured diagnostic field, returning `self` so attachments
+    /// chain.
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    /// The structured failure mode.
+    pub fn kind(&self) -> &PersonErrorKind {
+        &self.kind
+    }
+
+    /// The diagnostic fields attached to this error.
+    pub fn extensions(&self) -> &HashMap<String, Value> {
+        &self.extensions
+    }
+}
+
+impl fmt::Display for PersonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PersonErrorKind::NotFound { id } => write!(f, "no person found for {id}"),
+            PersonErrorKind::QueryFailed { source } => write!(f, "query failed: {source}"),
+            PersonErrorKind::Backend(message) => write!(f, "backend error: {message}"),
+        }
+    }
+}
+
+impl Error for PersonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            PersonErrorKind::QueryFailed { source } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl ProvideError for PersonError {
+    fn provide<'a>(&'a self, demand: &mut Demand<'a>) {
+        demand.provide_ref::<HashMap<String, Value>>(&self.extensions);
+    }
 }
 
 pub trait PersonQuerier: PersonContext + HasError {
     fn query_person(&self, person_id: &Self::PersonId) -> Result<Self::Person, Self::Error>;
+
+    /// Resolve a batch of persons in a single call so callers driving high
+    /// request volumes don't pay per-call overhead. Defaults to querying each
+    /// id in turn; a backend that can batch (one SQL `IN`, one HTTP round
+    /// trip) should override this.
+    fn query_persons(
+        &self,
+        person_ids: &[Self::PersonId],
+    ) -> Result<Vec<Self::Person>, Self::Error> {
+        person_ids
+            .iter()
+            .map(|person_id| self.query_person(person_id))
+            .collect()
+    }
+}
+
+/// A greeter that resolves and greets a whole slice of persons in one call.
+pub trait BatchGreeter<Context>
+where
+    Context: PersonContext + HasError,
+{
+    fn greet_all(
+        &self,
+        context: &Context,
+        person_ids: &[Context::PersonId],
+    ) -> Result<(), Context::Error>;
+}
+
+/// A pool of person ids that can be recursively halved across worker threads.
+pub trait PersonRequestGenerator {
+    type PersonId;
+
+    /// Divide the pool in two. Returns the left half plus `Some(right half)`
+    /// while more than one id remains, and `None` when the generator can no
+    /// longer be divided.
+    fn split(self) -> (Self, Option<Self>)
+    where
+        Self: Sized;
+
+    /// Draw the next id from this pool, or `None` once it is drained.
+    fn next(&mut self) -> Option<Self::PersonId>;
 }
 
 
@@ -26,4 +166,110 @@ where
         context: &Context,
         person_id: &Context::PersonId,
     ) -> Result<(), Context::Error>;
-}
\ No newline at end of file
+}
+
+/// Async sibling of [`PersonQuerier`] for contexts backed by a network or
+/// database rather than an in-memory lookup.
+#[allow(async_fn_in_trait)]
+pub trait AsyncPersonQuerier: PersonContext + HasError {
+    async fn query_person(&self, person_id: &Self::PersonId)
+        -> Result<Self::Person, Self::Error>;
+}
+
+/// Async sibling of [`Greeter`]. Keeps the same associated-type plumbing so an
+/// async context composes exactly like the synchronous one.
+#[allow(async_fn_in_trait)]
+pub trait AsyncGreeter<Context>
+where
+    Context: PersonContext + HasError,
+{
+    async fn greet(
+        &self,
+        context: &Context,
+        person_id: &Context::PersonId,
+    ) -> Result<(), Context::Error>;
+}
+
+/// A type-erased slot used to pull a value of a statically requested type out
+/// of an error without knowing its concrete type.
+///
+/// `Demand` carries the [`TypeId`] of the type a caller is asking for and a
+/// place to stash the answer. An error fills it through
+/// [`provide_ref`](Demand::provide_ref) / [`provide_value`](Demand::provide_value);
+/// both are no-ops unless the requested `TypeId` matches.
+pub struct Demand<'a> {
+    type_id: TypeId,
+    reference: Option<&'a dyn Any>,
+    value: Option<Box<dyn Any>>,
+}
+
+impl<'a> Demand<'a> {
+    fn request<T: 'static>() -> Self {
+        Demand {
+            type_id: TypeId::of::<T>(),
+            reference: None,
+            value: None,
+        }
+    }
+
+    /// Fill the demand with a borrowed `T`, if that is what was requested and
+    /// the slot is still empty.
+    pub fn provide_ref<T: 'static>(&mut self, value: &'a T) -> &mut Self {
+        if self.reference.is_none() && self.type_id == TypeId::of::<T>() {
+            self.reference = Some(value);
+        }
+        self
+    }
+
+    /// Fill the demand with an owned `T`, if that is what was requested and the
+    /// slot is still empty.
+    pub fn provide_value<T: 'static>(&mut self, value: T) -> &mut Self {
+        if self.value.is_none() && self.type_id == TypeId::of::<T>() {
+            self.value = Some(Box::new(value));
+        }
+        self
+    }
+
+    fn take_ref<T: 'static>(self) -> Option<&'a T> {
+        self.reference.and_then(|any| any.downcast_ref::<T>())
+    }
+
+    fn take_value<T: 'static>(self) -> Option<T> {
+        self.value
+            .and_then(|any| any.downcast::<T>().ok())
+            .map(|boxed| *boxed)
+    }
+}
+
+/// The error side of a context exposes structured data generically, in the
+/// style of generic member access: a caller demands a type and the error fills
+/// the slot only when it can.
+pub trait ProvideError {
+    /// Offer whatever typed data this error carries into `demand`. Must be a
+    /// no-op for any `TypeId` the error does not recognise.
+    fn provide<'a>(&'a self, demand: &mut Demand<'a>);
+}
+
+/// Request a reference to a `T` carried by `error`, or `None` if the error does
+/// not provide one.
+pub fn request_ref<T, E>(error: &E) -> Option<&T>
+where
+    T: 'static,
+    E: ProvideError + ?Sized,
+{
+    let mut demand = Demand::request::<T>();
+    error.provide(&mut demand);
+    demand.take_ref::<T>()
+}
+
+/// Request an owned `T` produced by `error`, or `None` if the error does not
+/// provide one.
+pub fn request_value<T, E>(error: &E) -> Option<T>
+where
+    T: 'static,
+    E: ProvideError + ?Sized,
+{
+    let mut demand = Demand::request::<T>();
+    error.provide(&mut demand);
+    demand.take_value::<T>()
+}